@@ -0,0 +1,37 @@
+use std::fmt::Debug;
+
+/// A `Aggregate` is the fundamental component responsible for enforcing business rules for
+/// any [`Command`](crate::command::Command) it receives, and holding the state needed to do so.
+pub trait Aggregate: Default {
+    /// The unique type identifier for this aggregate, used to partition its events in the store.
+    fn aggregate_type() -> &'static str;
+
+    /// Bumped whenever this aggregate's persisted shape changes incompatibly, so a
+    /// [`SnapshotStore`](crate::store::SnapshotStore) can tell a snapshot taken against an
+    /// older shape is stale and must be rebuilt from the event stream instead of trusted.
+    /// Defaults to `0` for aggregates that have never changed shape.
+    fn aggregate_snapshot_version() -> u32 {
+        0
+    }
+}
+
+/// Identifies a single instance of an [`Aggregate`] within the event store.
+pub trait AggregateId<A: Aggregate>: ToString {
+    /// The aggregate type this id resolves to.
+    fn aggregate_type(&self) -> &'static str {
+        A::aggregate_type()
+    }
+}
+
+/// The base error type, returned any time an aggregate fails to process a command or the
+/// framework fails to persist the resulting events.
+#[derive(Debug, PartialEq)]
+pub enum AggregateError {
+    /// A business rule was violated while handling a command.
+    UserError(String),
+    /// An unexpected, non-domain error occurred (e.g. a storage failure).
+    TechnicalError(String),
+    /// The aggregate was modified by another writer between load and commit; the observed
+    /// `expected_sequence` no longer matches the persisted tail sequence.
+    OptimisticConcurrency,
+}
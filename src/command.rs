@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use crate::aggregate::{Aggregate, AggregateError, AggregateId};
+use crate::event::DomainEvent;
+
+/// A `Command` carries the intent to change an [`Aggregate`]; handling it either produces the
+/// [`DomainEvent`]s describing what happened or rejects the change with an [`AggregateError`].
+pub trait Command<A: Aggregate, E: DomainEvent<A>> {
+    /// Validates the command against the current aggregate state and returns the events it
+    /// produces, without mutating `aggregate` itself.
+    fn handle(&self, aggregate: &mut A) -> Result<Vec<E>, AggregateError>;
+
+    /// A short, stable label identifying this command variant in the audit trail, e.g. the
+    /// struct's name.
+    fn command_type(&self) -> &'static str;
+
+    /// Serializes this command's own fields for storage in a [`StorableCommand`], so the
+    /// audit trail records exactly what was asked and not just what happened.
+    fn to_storable_details(&self) -> String;
+}
+
+/// A persisted record of a single command dispatched against an aggregate, kept alongside
+/// the event stream by a [`CommandStore`] so operators can audit *what was asked* and not
+/// just *what happened*.
+///
+/// Recorded for every dispatched command, whether or not it was ultimately committed — see
+/// [`CommandOutcome`].
+#[derive(Clone, Debug)]
+pub struct StorableCommand {
+    pub aggregate_id: String,
+    pub aggregate_type: String,
+    pub command_type: String,
+    pub details: String,
+    /// Zero-width (`sequence_range.0 == sequence_range.1`) when [`CommandOutcome::Rejected`],
+    /// since no event was committed to carry a real sequence.
+    pub sequence_range: (usize, usize),
+    pub actor: Option<String>,
+    pub metadata: HashMap<String, String>,
+    pub time: DateTime<Utc>,
+    pub outcome: CommandOutcome,
+}
+
+/// Whether a [`StorableCommand`] was committed, or rejected before anything could be written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandOutcome {
+    /// The command's events were committed at the recorded `sequence_range`.
+    Committed,
+    /// The command was rejected before any event reached the store; the message is the
+    /// rejecting [`AggregateError`] formatted for the audit trail.
+    Rejected(String),
+}
+
+/// Filters applied by [`CommandStore::load_history`]. A `None` field matches every command;
+/// a `Some` actor keeps only commands recorded for that actor, and a `Some` range keeps only
+/// commands whose corresponding [`StorableCommand`] field falls within the given inclusive
+/// bounds.
+#[derive(Clone, Debug, Default)]
+pub struct CommandHistoryCriteria {
+    pub time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    pub actor: Option<String>,
+    pub sequence_range: Option<(usize, usize)>,
+}
+
+/// The result of a [`CommandStore::load_history`] query.
+#[derive(Clone, Debug, Default)]
+pub struct CommandHistory {
+    pub commands: Vec<StorableCommand>,
+}
+
+/// Persists and retrieves the command audit trail for an aggregate, independent of the
+/// event stream the commands produced.
+pub trait CommandStore<I: AggregateId<A>, A: Aggregate> {
+    /// Appends `command` to the audit trail for the aggregate it was dispatched against.
+    fn append_command(&self, command: StorableCommand);
+    /// Returns the commands recorded for `id` that match `criteria`.
+    fn load_history(&self, id: &I, criteria: CommandHistoryCriteria) -> CommandHistory;
+}
+
+/// A `CommandStore` that records nothing, used as the default so existing frameworks keep
+/// their current behavior without opting in to command history.
+pub struct NoopCommandStore;
+
+impl<I: AggregateId<A>, A: Aggregate> CommandStore<I, A> for NoopCommandStore {
+    fn append_command(&self, _command: StorableCommand) {}
+
+    fn load_history(&self, _id: &I, _criteria: CommandHistoryCriteria) -> CommandHistory {
+        CommandHistory::default()
+    }
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+/// Supplies the metadata attached to every event wrapped by [`CqrsFramework`](crate::cqrs::CqrsFramework).
+pub trait MetadataSupplier {
+    /// Returns the metadata to stamp on the events produced by the current `execute` call.
+    fn supply(&self) -> HashMap<String, String>;
+
+    /// The id of the triggering command or event, threaded onto every event produced by the
+    /// current `execute` call so downstream projections can trace causality chains across
+    /// aggregates. Returns `None` by default.
+    fn correlation_id(&self) -> Option<String> {
+        None
+    }
+
+    /// The id of the command or event that directly caused the current `execute` call.
+    /// Returns `None` by default.
+    fn causation_id(&self) -> Option<String> {
+        None
+    }
+
+    /// The identity of whoever triggered the current `execute` call, recorded on its
+    /// [`StorableCommand`](crate::command::StorableCommand) entry. Returns `None` by default.
+    fn actor(&self) -> Option<String> {
+        None
+    }
+}
@@ -1,52 +1,133 @@
 use std::marker::PhantomData;
-use std::rc::Rc;
+use std::sync::Arc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use crate::aggregate::{AggregateId, Aggregate, AggregateError};
 use crate::event::{DomainEvent, MessageEnvelope};
-use crate::store::EventStore;
+use crate::store::{AsyncEventStore, EventStore, NoopSnapshotStore, SnapshotStore};
 use crate::config::MetadataSupplier;
-use crate::view::ViewProcessor;
-use crate::command::Command;
+use crate::view::{PostSaveEventListener, PreSaveEventListener, ViewProcessor};
+use crate::command::{Command, CommandHistoryCriteria, CommandOutcome, CommandStore, NoopCommandStore, StorableCommand};
+
+/// The default number of committed events between automatic snapshots, used when a
+/// `CqrsFramework` is built with [`CqrsFramework::new`] rather than [`CqrsFramework::with_snapshots`].
+const DEFAULT_SNAPSHOT_INTERVAL: usize = 100;
 
 /// This is the base framework for applying commands to produce events.
-pub struct CqrsFramework<I, A, E, ES, M>
+pub struct CqrsFramework<I, A, E, ES, M, SS = NoopSnapshotStore, CS = NoopCommandStore>
     where
         I: AggregateId<A>,
-        A: Aggregate,
+        A: Aggregate + Serialize + DeserializeOwned,
         E: DomainEvent<A>,
         ES: EventStore<I, A, E>,
-        M: MetadataSupplier
+        M: MetadataSupplier,
+        SS: SnapshotStore<I, A>,
+        CS: CommandStore<I, A>
 {
     store: ES,
-    view: Rc<dyn ViewProcessor<I, A, E>>,
+    pre_save_listeners: Vec<Arc<dyn PreSaveEventListener<I, A, E>>>,
+    post_save_listeners: Vec<Arc<dyn PostSaveEventListener<I, A, E>>>,
     metadata_supplier: M,
+    snapshot_store: SS,
+    snapshot_interval: usize,
+    command_store: CS,
     _phantom: PhantomData<I>,
 }
 
-impl<I, A, E, ES, M> CqrsFramework<I, A, E, ES, M>
+impl<I, A, E, ES, M> CqrsFramework<I, A, E, ES, M, NoopSnapshotStore, NoopCommandStore>
     where
         I: AggregateId<A>,
-        A: Aggregate,
+        A: Aggregate + Serialize + DeserializeOwned,
         E: DomainEvent<A>,
         ES: EventStore<I, A, E>,
         M: MetadataSupplier
 {
-    /// Creates new framework for dispatching commands using the provided elements.
-    pub fn new(store: ES, view: Rc<dyn ViewProcessor<I, A, E>>, metadata_supplier: M) -> CqrsFramework<I, A, E, ES, M>
-        where
-            I: AggregateId<A>,
-            A: Aggregate,
-            E: DomainEvent<A>,
-            ES: EventStore<I, A, E>,
-            M: MetadataSupplier
-    {
+    /// Creates new framework for dispatching commands using the provided elements. Aggregates
+    /// are always rebuilt from the full event stream; use [`CqrsFramework::with_snapshots`] to
+    /// enable snapshotting.
+    pub fn new<V: ViewProcessor<I, A, E> + 'static>(store: ES, view: Arc<V>, metadata_supplier: M) -> Self {
         CqrsFramework {
             store,
-            view,
+            pre_save_listeners: Vec::new(),
+            post_save_listeners: vec![view as Arc<dyn PostSaveEventListener<I, A, E>>],
             metadata_supplier,
+            snapshot_store: NoopSnapshotStore,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            command_store: NoopCommandStore,
+            _phantom: PhantomData::<I>,
+        }
+    }
+}
+
+impl<I, A, E, ES, M, SS> CqrsFramework<I, A, E, ES, M, SS, NoopCommandStore>
+    where
+        I: AggregateId<A>,
+        A: Aggregate + Serialize + DeserializeOwned,
+        E: DomainEvent<A>,
+        ES: EventStore<I, A, E>,
+        M: MetadataSupplier,
+        SS: SnapshotStore<I, A>
+{
+    /// Creates a new framework that consults `snapshot_store` before replaying events, and
+    /// writes a fresh snapshot every `snapshot_interval` committed events.
+    pub fn with_snapshots<V: ViewProcessor<I, A, E> + 'static>(store: ES, view: Arc<V>, metadata_supplier: M, snapshot_store: SS, snapshot_interval: usize) -> Self {
+        CqrsFramework {
+            store,
+            pre_save_listeners: Vec::new(),
+            post_save_listeners: vec![view as Arc<dyn PostSaveEventListener<I, A, E>>],
+            metadata_supplier,
+            snapshot_store,
+            snapshot_interval,
+            command_store: NoopCommandStore,
+            _phantom: PhantomData::<I>,
+        }
+    }
+}
+
+impl<I, A, E, ES, M, SS, CS> CqrsFramework<I, A, E, ES, M, SS, CS>
+    where
+        I: AggregateId<A>,
+        A: Aggregate + Serialize + DeserializeOwned,
+        E: DomainEvent<A>,
+        ES: EventStore<I, A, E>,
+        M: MetadataSupplier,
+        SS: SnapshotStore<I, A>,
+        CS: CommandStore<I, A>
+{
+    /// Records every dispatched command's audit trail to `command_store`, in addition to
+    /// committing the events it produces.
+    pub fn with_command_store<CS2: CommandStore<I, A>>(self, command_store: CS2) -> CqrsFramework<I, A, E, ES, M, SS, CS2> {
+        CqrsFramework {
+            store: self.store,
+            pre_save_listeners: self.pre_save_listeners,
+            post_save_listeners: self.post_save_listeners,
+            metadata_supplier: self.metadata_supplier,
+            snapshot_store: self.snapshot_store,
+            snapshot_interval: self.snapshot_interval,
+            command_store,
             _phantom: PhantomData::<I>,
         }
     }
 
+    /// Registers a listener that is invoked with the events about to be committed, before
+    /// the write happens. Any error it returns aborts the whole `execute` call.
+    pub fn append_pre_save_listener(mut self, listener: Arc<dyn PreSaveEventListener<I, A, E>>) -> Self {
+        self.pre_save_listeners.push(listener);
+        self
+    }
+
+    /// Registers an additional listener to run after a successful commit, alongside the
+    /// [`ViewProcessor`] supplied at construction.
+    pub fn append_post_save_listener(mut self, listener: Arc<dyn PostSaveEventListener<I, A, E>>) -> Self {
+        self.post_save_listeners.push(listener);
+        self
+    }
+
+    /// Returns the recorded command audit trail for `aggregate_id` matching `criteria`.
+    pub fn command_history(&self, aggregate_id: &I, criteria: CommandHistoryCriteria) -> crate::command::CommandHistory {
+        self.command_store.load_history(aggregate_id, criteria)
+    }
+
     /// This applies a command to an aggregate, this is the only way to make any change to
     /// the state of an aggregate.
     ///
@@ -55,16 +136,81 @@ impl<I, A, E, ES, M> CqrsFramework<I, A, E, ES, M>
     ///
     /// If successful the events produced will be applied to the [`ViewProcessor`].
     pub fn execute<C: Command<A, E>>(&self, aggregate_id: &I, command: C) -> Result<(), AggregateError> {
+        let _lock = self.store.lock(aggregate_id);
         let (mut aggregate, current_sequence) = self.load_aggregate(aggregate_id);
-        let resultant_events = command.handle(&mut aggregate)?;
+
+        let resultant_events = match command.handle(&mut aggregate) {
+            Ok(events) => events,
+            Err(error) => return self.reject(aggregate_id, &command, current_sequence, error),
+        };
         let wrapped_events = self.wrap_events(aggregate_id, current_sequence, resultant_events);
 
-        let committed_events = <CqrsFramework<I, A, E, ES, M>>::duplicate(&wrapped_events);
-        self.store.commit(wrapped_events)?;
-        self.view.dispatch(&aggregate_id, committed_events);
+        for listener in &self.pre_save_listeners {
+            if let Err(error) = listener.handle(aggregate_id, &wrapped_events) {
+                return self.reject(aggregate_id, &command, current_sequence, error);
+            }
+        }
+
+        let committed_events = <CqrsFramework<I, A, E, ES, M, SS, CS>>::duplicate(&wrapped_events);
+        if let Err(error) = self.store.commit(wrapped_events, current_sequence) {
+            return self.reject(aggregate_id, &command, current_sequence, error);
+        }
+
+        let mut final_sequence = current_sequence;
+        for envelope in &committed_events {
+            final_sequence = envelope.sequence;
+            envelope.payload.clone().apply(&mut aggregate);
+        }
+        self.maybe_save_snapshot(aggregate_id, &aggregate, current_sequence, final_sequence);
+        self.append_storable_command(aggregate_id, &command, current_sequence, final_sequence, CommandOutcome::Committed);
+
+        for listener in &self.post_save_listeners {
+            let events = <CqrsFramework<I, A, E, ES, M, SS, CS>>::duplicate(&committed_events);
+            listener.handle(aggregate_id, events);
+        }
         Ok(())
     }
 
+    /// Records `command` as rejected at `current_sequence` (a zero-width `sequence_range`,
+    /// since nothing was committed) before returning `error`, so the audit trail captures
+    /// what was asked even for commands that never produced an event.
+    fn reject<C: Command<A, E>>(&self, aggregate_id: &I, command: &C, current_sequence: usize, error: AggregateError) -> Result<(), AggregateError> {
+        self.append_storable_command(aggregate_id, command, current_sequence, current_sequence, CommandOutcome::Rejected(format!("{:?}", error)));
+        Err(error)
+    }
+
+    /// Builds a [`StorableCommand`] record from `command`, the sequence range it produced
+    /// and its `outcome`, and appends it to the command audit trail.
+    fn append_storable_command<C: Command<A, E>>(&self, aggregate_id: &I, command: &C, previous_sequence: usize, final_sequence: usize, outcome: CommandOutcome) {
+        let metadata = self.metadata_supplier.supply();
+        let actor = self.metadata_supplier.actor();
+        self.command_store.append_command(StorableCommand {
+            aggregate_id: aggregate_id.to_string(),
+            aggregate_type: aggregate_id.aggregate_type().to_string(),
+            command_type: command.command_type().to_string(),
+            details: command.to_storable_details(),
+            sequence_range: (previous_sequence, final_sequence),
+            actor,
+            metadata,
+            time: chrono::Utc::now(),
+            outcome,
+        });
+    }
+
+    /// Saves a fresh snapshot once `current_sequence` has crossed a `snapshot_interval`
+    /// boundary since `previous_sequence`, so snapshots are taken roughly every N events
+    /// rather than on every single commit.
+    fn maybe_save_snapshot(&self, aggregate_id: &I, aggregate: &A, previous_sequence: usize, current_sequence: usize) {
+        if self.snapshot_interval == 0 {
+            return;
+        }
+        let previous_interval = previous_sequence / self.snapshot_interval;
+        let current_interval = current_sequence / self.snapshot_interval;
+        if current_interval > previous_interval {
+            self.snapshot_store.save_snapshot(aggregate_id, aggregate, current_sequence, A::aggregate_snapshot_version());
+        }
+    }
+
     fn duplicate(wrapped_events: &[MessageEnvelope<A, E>]) -> Vec<MessageEnvelope<A, E>> {
         let mut committed_events = Vec::new();
         for wrapped_event in wrapped_events {
@@ -74,30 +220,37 @@ impl<I, A, E, ES, M> CqrsFramework<I, A, E, ES, M>
     }
 
     fn wrap_events(&self, aggregate_id: &I, current_sequence: usize, resultant_events: Vec<E>) -> Vec<MessageEnvelope<A, E>> {
-        let mut sequence = current_sequence;
         let mut wrapped_events: Vec<MessageEnvelope<A, E>> = Vec::new();
-        for payload in resultant_events {
-            sequence += 1;
+        for (sequence, payload) in ((current_sequence + 1)..).zip(resultant_events) {
             let aggregate_type = aggregate_id.aggregate_type().to_string();
             let aggregate_id: String = aggregate_id.to_string();
-            let sequence = sequence;
             let metadata = self.metadata_supplier.supply();
+            let correlation_id = self.metadata_supplier.correlation_id();
+            let causation_id = self.metadata_supplier.causation_id();
             wrapped_events.push(MessageEnvelope {
                 aggregate_id,
                 sequence,
                 aggregate_type,
                 payload,
                 metadata,
+                time: chrono::Utc::now(),
+                correlation_id,
+                causation_id,
                 _phantom: PhantomData,
             });
         }
         wrapped_events
     }
 
+    /// Rebuilds the current state of the aggregate identified by `aggregate_id`, starting
+    /// from the most recent snapshot (if any) and replaying only the events committed since.
     fn load_aggregate(&self, aggregate_id: &I) -> (A, usize) {
-        let committed_events = self.store.load(aggregate_id);
-        let mut aggregate = A::default();
-        let mut current_sequence = 0;
+        let (mut aggregate, snapshot_sequence) = match self.snapshot_store.load_snapshot(aggregate_id, A::aggregate_snapshot_version()) {
+            Some((aggregate, sequence)) => (aggregate, sequence),
+            None => (A::default(), 0),
+        };
+        let committed_events = self.store.load_since(aggregate_id, snapshot_sequence);
+        let mut current_sequence = snapshot_sequence;
         for envelope in committed_events {
             current_sequence = envelope.sequence;
             let event = envelope.payload;
@@ -105,4 +258,531 @@ impl<I, A, E, ES, M> CqrsFramework<I, A, E, ES, M>
         }
         (aggregate, current_sequence)
     }
+}
+
+impl<I, A, E, ES, M, SS, CS> CqrsFramework<I, A, E, ES, M, SS, CS>
+    where
+        I: AggregateId<A> + Send + Sync,
+        A: Aggregate + Serialize + DeserializeOwned + Send + Sync + 'static,
+        E: DomainEvent<A> + Send + Sync + 'static,
+        ES: EventStore<I, A, E> + AsyncEventStore<I, A, E>,
+        M: MetadataSupplier,
+        SS: SnapshotStore<I, A>,
+        CS: CommandStore<I, A>
+{
+    /// The async counterpart to [`CqrsFramework::execute`], for event stores that talk to a
+    /// real database or message broker and can't be driven without blocking. `ES` must
+    /// additionally implement [`AsyncEventStore`] — an in-memory synchronous store can satisfy
+    /// this via [`crate::store::BlockingEventStoreAdapter`] until a true async store exists.
+    ///
+    /// Only the event store is awaited asynchronously; [`ViewProcessor`] and the other
+    /// post-save/pre-save listeners are still invoked synchronously, same as in `execute`. Views
+    /// are expected to be in-memory projections or otherwise cheap to update, so there is no
+    /// async counterpart to [`ViewProcessor`] — register one through an async-aware listener of
+    /// your own if a view genuinely needs to await I/O.
+    pub async fn execute_async<C: Command<A, E>>(&self, aggregate_id: &I, command: C) -> Result<(), AggregateError> {
+        let (mut aggregate, current_sequence) = {
+            let (mut aggregate, snapshot_sequence) = match self.snapshot_store.load_snapshot(aggregate_id, A::aggregate_snapshot_version()) {
+                Some((aggregate, sequence)) => (aggregate, sequence),
+                None => (A::default(), 0),
+            };
+            let committed_events = AsyncEventStore::load_since(&self.store, aggregate_id, snapshot_sequence).await;
+            let mut current_sequence = snapshot_sequence;
+            for envelope in committed_events {
+                current_sequence = envelope.sequence;
+                envelope.payload.apply(&mut aggregate);
+            }
+            (aggregate, current_sequence)
+        };
+
+        let resultant_events = match command.handle(&mut aggregate) {
+            Ok(events) => events,
+            Err(error) => return self.reject(aggregate_id, &command, current_sequence, error),
+        };
+        let wrapped_events = self.wrap_events(aggregate_id, current_sequence, resultant_events);
+
+        for listener in &self.pre_save_listeners {
+            if let Err(error) = listener.handle(aggregate_id, &wrapped_events) {
+                return self.reject(aggregate_id, &command, current_sequence, error);
+            }
+        }
+
+        let committed_events = <CqrsFramework<I, A, E, ES, M, SS, CS>>::duplicate(&wrapped_events);
+        if let Err(error) = AsyncEventStore::commit(&self.store, wrapped_events, current_sequence).await {
+            return self.reject(aggregate_id, &command, current_sequence, error);
+        }
+
+        let mut final_sequence = current_sequence;
+        for envelope in &committed_events {
+            final_sequence = envelope.sequence;
+            envelope.payload.clone().apply(&mut aggregate);
+        }
+        self.maybe_save_snapshot(aggregate_id, &aggregate, current_sequence, final_sequence);
+        self.append_storable_command(aggregate_id, &command, current_sequence, final_sequence, CommandOutcome::Committed);
+
+        for listener in &self.post_save_listeners {
+            let events = <CqrsFramework<I, A, E, ES, M, SS, CS>>::duplicate(&committed_events);
+            listener.handle(aggregate_id, events);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use serde::{Deserialize, Serialize};
+    use crate::aggregate::{Aggregate, AggregateError, AggregateId};
+    use crate::command::{Command, CommandHistoryCriteria, CommandOutcome, CommandStore, StorableCommand};
+    use crate::config::MetadataSupplier;
+    use crate::event::{DomainEvent, MessageEnvelope};
+    use crate::store::{AsyncEventStore, EventStore, SnapshotStore};
+    use crate::view::{PostSaveEventListener, PreSaveEventListener, ViewProcessor};
+    use super::CqrsFramework;
+
+    #[derive(Default, Clone, Serialize, Deserialize)]
+    struct Counter {
+        value: i64,
+    }
+
+    impl Aggregate for Counter {
+        fn aggregate_type() -> &'static str {
+            "counter"
+        }
+    }
+
+    #[derive(Clone)]
+    enum CounterEvent {
+        Incremented(i64),
+    }
+
+    impl DomainEvent<Counter> for CounterEvent {
+        fn apply(self, aggregate: &mut Counter) {
+            match self {
+                CounterEvent::Incremented(amount) => aggregate.value += amount,
+            }
+        }
+    }
+
+    struct Increment(i64);
+
+    impl Command<Counter, CounterEvent> for Increment {
+        fn handle(&self, _aggregate: &mut Counter) -> Result<Vec<CounterEvent>, AggregateError> {
+            Ok(vec![CounterEvent::Incremented(self.0)])
+        }
+
+        fn command_type(&self) -> &'static str {
+            "Increment"
+        }
+
+        fn to_storable_details(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    /// A command whose handler always rejects, used to exercise the error path.
+    struct RejectEverything;
+
+    impl Command<Counter, CounterEvent> for RejectEverything {
+        fn handle(&self, _aggregate: &mut Counter) -> Result<Vec<CounterEvent>, AggregateError> {
+            Err(AggregateError::UserError("rejected".to_string()))
+        }
+
+        fn command_type(&self) -> &'static str {
+            "RejectEverything"
+        }
+
+        fn to_storable_details(&self) -> String {
+            String::new()
+        }
+    }
+
+    struct CounterId(&'static str);
+
+    impl std::fmt::Display for CounterId {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl AggregateId<Counter> for CounterId {}
+
+    #[derive(Default)]
+    struct InMemoryEventStore {
+        events: Mutex<Vec<MessageEnvelope<Counter, CounterEvent>>>,
+    }
+
+    impl EventStore<CounterId, Counter, CounterEvent> for InMemoryEventStore {
+        fn load(&self, aggregate_id: &CounterId) -> Vec<MessageEnvelope<Counter, CounterEvent>> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|envelope| envelope.aggregate_id == aggregate_id.to_string())
+                .cloned()
+                .collect()
+        }
+
+        fn commit(&self, events: Vec<MessageEnvelope<Counter, CounterEvent>>, expected_sequence: usize) -> Result<(), AggregateError> {
+            let Some(first) = events.first() else {
+                // A command handler is allowed to return no events (an idempotent no-op);
+                // there is nothing to check the sequence against or append in that case.
+                return Ok(());
+            };
+            let mut store = self.events.lock().unwrap();
+            let aggregate_id = first.aggregate_id.clone();
+            let tail = store
+                .iter()
+                .filter(|envelope| envelope.aggregate_id == aggregate_id)
+                .map(|envelope| envelope.sequence)
+                .max()
+                .unwrap_or(0);
+            if tail != expected_sequence {
+                return Err(AggregateError::OptimisticConcurrency);
+            }
+            store.extend(events);
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncEventStore<CounterId, Counter, CounterEvent> for InMemoryEventStore {
+        async fn load(&self, aggregate_id: &CounterId) -> Vec<MessageEnvelope<Counter, CounterEvent>> {
+            EventStore::load(self, aggregate_id)
+        }
+
+        async fn commit(&self, events: Vec<MessageEnvelope<Counter, CounterEvent>>, expected_sequence: usize) -> Result<(), AggregateError> {
+            EventStore::commit(self, events, expected_sequence)
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemorySnapshotStore {
+        snapshots: Mutex<Option<(Counter, usize, u32)>>,
+    }
+
+    impl SnapshotStore<CounterId, Counter> for InMemorySnapshotStore {
+        fn load_snapshot(&self, _id: &CounterId, expected_version: u32) -> Option<(Counter, usize)> {
+            self.snapshots
+                .lock()
+                .unwrap()
+                .clone()
+                .filter(|(_, _, version)| *version == expected_version)
+                .map(|(aggregate, sequence, _)| (aggregate, sequence))
+        }
+
+        fn save_snapshot(&self, _id: &CounterId, aggregate: &Counter, sequence: usize, version: u32) {
+            *self.snapshots.lock().unwrap() = Some((aggregate.clone(), sequence, version));
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryCommandStore {
+        commands: Mutex<Vec<StorableCommand>>,
+    }
+
+    impl CommandStore<CounterId, Counter> for InMemoryCommandStore {
+        fn append_command(&self, command: StorableCommand) {
+            self.commands.lock().unwrap().push(command);
+        }
+
+        fn load_history(&self, id: &CounterId, criteria: CommandHistoryCriteria) -> crate::command::CommandHistory {
+            let commands = self
+                .commands
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|command| command.aggregate_id == id.to_string())
+                .filter(|command| criteria.actor.is_none() || command.actor == criteria.actor)
+                .filter(|command| {
+                    criteria.time_range.is_none_or(|(from, to)| command.time >= from && command.time <= to)
+                })
+                .filter(|command| {
+                    criteria.sequence_range.is_none_or(|(from, to)| {
+                        command.sequence_range.0 >= from && command.sequence_range.1 <= to
+                    })
+                })
+                .cloned()
+                .collect();
+            crate::command::CommandHistory { commands }
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingView {
+        dispatched: Mutex<Vec<MessageEnvelope<Counter, CounterEvent>>>,
+    }
+
+    impl ViewProcessor<CounterId, Counter, CounterEvent> for RecordingView {
+        fn dispatch(&self, _aggregate_id: &CounterId, events: Vec<MessageEnvelope<Counter, CounterEvent>>) {
+            self.dispatched.lock().unwrap().extend(events);
+        }
+    }
+
+    struct VetoingPreSaveListener;
+
+    impl PreSaveEventListener<CounterId, Counter, CounterEvent> for VetoingPreSaveListener {
+        fn handle(&self, _aggregate_id: &CounterId, events: &[MessageEnvelope<Counter, CounterEvent>]) -> Result<(), AggregateError> {
+            if events.iter().any(|envelope| matches!(envelope.payload, CounterEvent::Incremented(amount) if amount < 0)) {
+                return Err(AggregateError::UserError("negative increments are not allowed".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingPostSaveListener {
+        handled: Mutex<usize>,
+    }
+
+    impl PostSaveEventListener<CounterId, Counter, CounterEvent> for RecordingPostSaveListener {
+        fn handle(&self, _aggregate_id: &CounterId, events: Vec<MessageEnvelope<Counter, CounterEvent>>) {
+            *self.handled.lock().unwrap() += events.len();
+        }
+    }
+
+    struct TestMetadataSupplier {
+        actor: Option<String>,
+        correlation_id: Option<String>,
+        causation_id: Option<String>,
+    }
+
+    impl MetadataSupplier for TestMetadataSupplier {
+        fn supply(&self) -> std::collections::HashMap<String, String> {
+            std::collections::HashMap::new()
+        }
+
+        fn correlation_id(&self) -> Option<String> {
+            self.correlation_id.clone()
+        }
+
+        fn causation_id(&self) -> Option<String> {
+            self.causation_id.clone()
+        }
+
+        fn actor(&self) -> Option<String> {
+            self.actor.clone()
+        }
+    }
+
+    fn metadata_supplier() -> TestMetadataSupplier {
+        TestMetadataSupplier {
+            actor: Some("alice".to_string()),
+            correlation_id: Some("corr-1".to_string()),
+            causation_id: Some("cause-1".to_string()),
+        }
+    }
+
+    #[test]
+    fn snapshot_replay_skips_events_already_folded_into_the_snapshot() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let cqrs = CqrsFramework::with_snapshots(
+            InMemoryEventStore::default(),
+            view.clone(),
+            metadata_supplier(),
+            InMemorySnapshotStore::default(),
+            2,
+        );
+        let id = CounterId("agg-1");
+
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, Increment(1)).unwrap();
+
+        // The snapshot was saved after the second event, at sequence 2.
+        let (snapshot, sequence) = cqrs.snapshot_store.load_snapshot(&id, Counter::aggregate_snapshot_version()).unwrap();
+        assert_eq!(sequence, 2);
+        assert_eq!(snapshot.value, 2);
+
+        let (aggregate, current_sequence) = cqrs.load_aggregate(&id);
+        assert_eq!(current_sequence, 3);
+        assert_eq!(aggregate.value, 3);
+
+        // Crossing the next interval boundary (sequence 4) refreshes the snapshot again,
+        // rather than leaving the one from the first boundary in place forever.
+        cqrs.execute(&id, Increment(1)).unwrap();
+        let (snapshot, sequence) = cqrs.snapshot_store.load_snapshot(&id, Counter::aggregate_snapshot_version()).unwrap();
+        assert_eq!(sequence, 4);
+        assert_eq!(snapshot.value, 4);
+    }
+
+    #[test]
+    fn load_since_excludes_events_at_or_below_the_given_sequence() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let cqrs = CqrsFramework::new(InMemoryEventStore::default(), view, metadata_supplier());
+        let id = CounterId("agg-1");
+
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, Increment(1)).unwrap();
+
+        let since = EventStore::load_since(&cqrs.store, &id, 1);
+        assert_eq!(since.iter().map(|envelope| envelope.sequence).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn stale_snapshot_version_is_treated_as_absent() {
+        let store = InMemorySnapshotStore::default();
+        let id = CounterId("agg-1");
+        store.save_snapshot(&id, &Counter { value: 5 }, 3, 1);
+
+        // A snapshot saved under a different version than the aggregate's current one must
+        // not be handed back to the caller, so it rebuilds from the event stream instead.
+        assert!(store.load_snapshot(&id, 2).is_none());
+
+        let (aggregate, sequence) = store.load_snapshot(&id, 1).unwrap();
+        assert_eq!(sequence, 3);
+        assert_eq!(aggregate.value, 5);
+    }
+
+    #[test]
+    fn optimistic_concurrency_rejects_a_commit_against_a_stale_sequence() {
+        let store = InMemoryEventStore::default();
+        let id = CounterId("agg-1");
+        let stale_events = vec![MessageEnvelope {
+            aggregate_id: id.to_string(),
+            sequence: 1,
+            aggregate_type: Counter::aggregate_type().to_string(),
+            payload: CounterEvent::Incremented(1),
+            metadata: std::collections::HashMap::new(),
+            time: chrono::Utc::now(),
+            correlation_id: None,
+            causation_id: None,
+            _phantom: std::marker::PhantomData,
+        }];
+        EventStore::commit(&store, stale_events.clone(), 0).unwrap();
+
+        let result = EventStore::commit(&store, stale_events, 0);
+        assert_eq!(result, Err(AggregateError::OptimisticConcurrency));
+    }
+
+    #[test]
+    fn committing_no_events_is_a_no_op_rather_than_a_panic() {
+        // A command handler returning `Ok(vec![])` (an idempotent no-op) must not crash the
+        // store it's committed through.
+        let store = InMemoryEventStore::default();
+        let id = CounterId("agg-1");
+
+        let result = EventStore::commit(&store, Vec::new(), 0);
+
+        assert_eq!(result, Ok(()));
+        assert!(EventStore::load(&store, &id).is_empty());
+    }
+
+    #[test]
+    fn pre_save_listener_veto_prevents_commit_and_post_save_fan_out() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let post_save = std::sync::Arc::new(RecordingPostSaveListener::default());
+        let cqrs = CqrsFramework::new(InMemoryEventStore::default(), view.clone(), metadata_supplier())
+            .append_pre_save_listener(std::sync::Arc::new(VetoingPreSaveListener))
+            .append_post_save_listener(post_save.clone());
+        let id = CounterId("agg-1");
+
+        let result = cqrs.execute(&id, Increment(-1));
+
+        assert_eq!(result, Err(AggregateError::UserError("negative increments are not allowed".to_string())));
+        assert!(EventStore::load(&cqrs.store, &id).is_empty());
+        assert!(view.dispatched.lock().unwrap().is_empty());
+        assert_eq!(*post_save.handled.lock().unwrap(), 0);
+
+        cqrs.execute(&id, Increment(1)).unwrap();
+        assert_eq!(view.dispatched.lock().unwrap().len(), 1);
+        assert_eq!(*post_save.handled.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn execute_async_awaits_the_async_event_store_and_still_dispatches_the_view() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let cqrs = CqrsFramework::new(InMemoryEventStore::default(), view.clone(), metadata_supplier());
+        let id = CounterId("agg-1");
+
+        futures::executor::block_on(cqrs.execute_async(&id, Increment(5))).unwrap();
+
+        assert_eq!(view.dispatched.lock().unwrap().len(), 1);
+        let (aggregate, sequence) = cqrs.load_aggregate(&id);
+        assert_eq!(sequence, 1);
+        assert_eq!(aggregate.value, 5);
+
+        // The event committed through `execute_async` is visible through the async load path
+        // too, not just the synchronous one `load_aggregate` happens to use above.
+        let events = futures::executor::block_on(AsyncEventStore::load(&cqrs.store, &id));
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn command_history_is_recorded_and_filterable_by_actor() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let cqrs = CqrsFramework::new(InMemoryEventStore::default(), view, metadata_supplier())
+            .with_command_store(InMemoryCommandStore::default());
+        let id = CounterId("agg-1");
+
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, RejectEverything).unwrap_err();
+
+        // Both the committed and the rejected command are recorded: the audit trail exists
+        // to show what was asked, not just what succeeded.
+        let history = cqrs.command_history(&id, CommandHistoryCriteria::default());
+        assert_eq!(history.commands.len(), 2);
+        assert_eq!(history.commands[0].actor.as_deref(), Some("alice"));
+        assert_eq!(history.commands[0].command_type, "Increment");
+        assert_eq!(history.commands[0].sequence_range, (0, 1));
+        assert_eq!(history.commands[0].outcome, CommandOutcome::Committed);
+        assert_eq!(history.commands[1].command_type, "RejectEverything");
+        assert_eq!(history.commands[1].sequence_range, (1, 1));
+        assert_eq!(
+            history.commands[1].outcome,
+            CommandOutcome::Rejected(format!("{:?}", AggregateError::UserError("rejected".to_string()))),
+        );
+
+        let no_match = cqrs.command_history(
+            &id,
+            CommandHistoryCriteria { actor: Some("bob".to_string()), ..Default::default() },
+        );
+        assert!(no_match.commands.is_empty());
+    }
+
+    #[test]
+    fn command_history_filters_by_sequence_and_time_range() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let cqrs = CqrsFramework::new(InMemoryEventStore::default(), view, metadata_supplier())
+            .with_command_store(InMemoryCommandStore::default());
+        let id = CounterId("agg-1");
+
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, Increment(1)).unwrap();
+        cqrs.execute(&id, Increment(1)).unwrap();
+
+        let all = cqrs.command_history(&id, CommandHistoryCriteria::default());
+        assert_eq!(all.commands.len(), 3);
+
+        let by_sequence = cqrs.command_history(
+            &id,
+            CommandHistoryCriteria { sequence_range: Some((1, 2)), ..Default::default() },
+        );
+        assert_eq!(by_sequence.commands.len(), 1);
+        assert_eq!(by_sequence.commands[0].sequence_range, (1, 2));
+
+        let middle_time = all.commands[1].time;
+        let by_time = cqrs.command_history(
+            &id,
+            CommandHistoryCriteria { time_range: Some((middle_time, middle_time)), ..Default::default() },
+        );
+        assert_eq!(by_time.commands.len(), 1);
+        assert_eq!(by_time.commands[0].sequence_range, (1, 2));
+    }
+
+    #[test]
+    fn correlation_and_causation_ids_are_stamped_onto_every_committed_event() {
+        let view = std::sync::Arc::new(RecordingView::default());
+        let cqrs = CqrsFramework::new(InMemoryEventStore::default(), view, metadata_supplier());
+        let id = CounterId("agg-1");
+
+        cqrs.execute(&id, Increment(1)).unwrap();
+
+        let events = EventStore::load(&cqrs.store, &id);
+        assert_eq!(events[0].correlation_id.as_deref(), Some("corr-1"));
+        assert_eq!(events[0].causation_id.as_deref(), Some("cause-1"));
+        assert_eq!(events[0].aggregate_type, Counter::aggregate_type());
+        assert!(events[0].time <= chrono::Utc::now());
+    }
 }
\ No newline at end of file
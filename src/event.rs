@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use chrono::{DateTime, Utc};
+use crate::aggregate::Aggregate;
+
+/// A `DomainEvent` represents a fact that has already happened to an [`Aggregate`], produced
+/// by applying a [`Command`](crate::command::Command).
+pub trait DomainEvent<A: Aggregate>: Clone {
+    /// Mutates the aggregate to reflect that this event has occurred.
+    fn apply(self, aggregate: &mut A);
+}
+
+/// Wraps a raw [`DomainEvent`] with the bookkeeping needed to persist and route it: the
+/// aggregate it belongs to, its position in that aggregate's event stream, and any metadata
+/// attached at the time it was produced.
+pub struct MessageEnvelope<A: Aggregate, E: DomainEvent<A>> {
+    pub aggregate_id: String,
+    pub sequence: usize,
+    pub aggregate_type: String,
+    pub payload: E,
+    pub metadata: HashMap<String, String>,
+    /// When this event occurred, stamped by `wrap_events` just before the pre-save listeners and the commit run.
+    pub time: DateTime<Utc>,
+    /// The id of the command or event that ultimately triggered this one, threaded across
+    /// aggregates so a full causality chain can be reconstructed.
+    pub correlation_id: Option<String>,
+    /// The id of the command or event that directly caused this one.
+    pub causation_id: Option<String>,
+    pub _phantom: PhantomData<A>,
+}
+
+// Hand-written so that cloning an envelope doesn't require `A: Clone` — `A` only ever
+// appears behind `PhantomData`, which is `Clone` regardless of `A`.
+impl<A: Aggregate, E: DomainEvent<A>> Clone for MessageEnvelope<A, E> {
+    fn clone(&self) -> Self {
+        MessageEnvelope {
+            aggregate_id: self.aggregate_id.clone(),
+            sequence: self.sequence,
+            aggregate_type: self.aggregate_type.clone(),
+            payload: self.payload.clone(),
+            metadata: self.metadata.clone(),
+            time: self.time,
+            correlation_id: self.correlation_id.clone(),
+            causation_id: self.causation_id.clone(),
+            _phantom: self._phantom,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod aggregate;
+pub mod command;
+pub mod config;
+pub mod cqrs;
+pub mod event;
+pub mod store;
+pub mod view;
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::aggregate::{Aggregate, AggregateError, AggregateId};
+use crate::event::{DomainEvent, MessageEnvelope};
+
+/// Persists and retrieves the committed event stream for an [`Aggregate`].
+pub trait EventStore<I: AggregateId<A>, A: Aggregate, E: DomainEvent<A>> {
+    /// Loads every event committed so far for `aggregate_id`, in sequence order.
+    fn load(&self, aggregate_id: &I) -> Vec<MessageEnvelope<A, E>>;
+
+    /// Loads only the events committed after `after_sequence`, in sequence order. Used by a
+    /// loader that already holds a snapshot, so it isn't forced to pay for reading (and
+    /// immediately discarding) every event folded into it.
+    ///
+    /// The default just filters the result of [`EventStore::load`], which still costs a full
+    /// read; a store backed by a real database or message broker should override this to push
+    /// the bound down into the query itself and actually save the I/O.
+    fn load_since(&self, aggregate_id: &I, after_sequence: usize) -> Vec<MessageEnvelope<A, E>> {
+        self.load(aggregate_id)
+            .into_iter()
+            .filter(|envelope| envelope.sequence > after_sequence)
+            .collect()
+    }
+
+    /// Persists `events`, which have already been assigned their sequence numbers, provided
+    /// `expected_sequence` (the tail sequence observed when the aggregate was loaded) still
+    /// matches the persisted tail. Implementations must reject the write with
+    /// [`AggregateError::OptimisticConcurrency`] when another writer has committed in the
+    /// meantime, rather than silently overwriting it.
+    fn commit(&self, events: Vec<MessageEnvelope<A, E>>, expected_sequence: usize) -> Result<(), AggregateError>;
+
+    /// Acquires an exclusive lock on `id`'s event stream, held across a load-then-commit
+    /// window to serialize writes per aggregate. Stores that only rely on
+    /// `expected_sequence` rejection in [`EventStore::commit`] can keep the no-op default.
+    fn lock(&self, _id: &I) -> EventStoreLockGuard {
+        Box::new(NoopLockGuard)
+    }
+}
+
+/// An RAII guard returned by [`EventStore::lock`]; the lock it represents is released when
+/// the guard is dropped.
+pub trait UnlockOnDrop {}
+
+/// A lock held across a single `execute` call's load-then-commit window, see [`EventStore::lock`].
+pub type EventStoreLockGuard = Box<dyn UnlockOnDrop>;
+
+struct NoopLockGuard;
+
+impl UnlockOnDrop for NoopLockGuard {}
+
+/// The async counterpart to [`EventStore`], for stores backed by a database or message
+/// broker that can't be driven without blocking. `CqrsFramework::execute_async` awaits these
+/// methods directly instead of calling through the synchronous trait.
+#[async_trait]
+pub trait AsyncEventStore<I, A, E>: Send + Sync
+    where
+        I: AggregateId<A> + Send + Sync,
+        A: Aggregate + Send + Sync + 'static,
+        E: DomainEvent<A> + Send + Sync + 'static
+{
+    /// Loads every event committed so far for `aggregate_id`, in sequence order.
+    async fn load(&self, aggregate_id: &I) -> Vec<MessageEnvelope<A, E>>;
+
+    /// The async counterpart to [`EventStore::load_since`]; see its documentation for why a
+    /// real store should override the default instead of inheriting it.
+    async fn load_since(&self, aggregate_id: &I, after_sequence: usize) -> Vec<MessageEnvelope<A, E>> {
+        self.load(aggregate_id)
+            .await
+            .into_iter()
+            .filter(|envelope| envelope.sequence > after_sequence)
+            .collect()
+    }
+
+    /// Persists `events`, rejecting the write with [`AggregateError::OptimisticConcurrency`]
+    /// if `expected_sequence` no longer matches the persisted tail.
+    async fn commit(&self, events: Vec<MessageEnvelope<A, E>>, expected_sequence: usize) -> Result<(), AggregateError>;
+}
+
+/// Adapts a synchronous [`EventStore`] into an [`AsyncEventStore`] by calling straight
+/// through, so an in-memory store can be used under `execute_async` before a real
+/// network-backed store exists.
+pub struct BlockingEventStoreAdapter<ES>(pub ES);
+
+#[async_trait]
+impl<I, A, E, ES> AsyncEventStore<I, A, E> for BlockingEventStoreAdapter<ES>
+    where
+        I: AggregateId<A> + Send + Sync,
+        A: Aggregate + Send + Sync + 'static,
+        E: DomainEvent<A> + Send + Sync + 'static,
+        ES: EventStore<I, A, E> + Send + Sync
+{
+    async fn load(&self, aggregate_id: &I) -> Vec<MessageEnvelope<A, E>> {
+        self.0.load(aggregate_id)
+    }
+
+    async fn load_since(&self, aggregate_id: &I, after_sequence: usize) -> Vec<MessageEnvelope<A, E>> {
+        self.0.load_since(aggregate_id, after_sequence)
+    }
+
+    async fn commit(&self, events: Vec<MessageEnvelope<A, E>>, expected_sequence: usize) -> Result<(), AggregateError> {
+        self.0.commit(events, expected_sequence)
+    }
+}
+
+impl<I: AggregateId<A>, A: Aggregate, E: DomainEvent<A>, ES: EventStore<I, A, E>> EventStore<I, A, E> for BlockingEventStoreAdapter<ES> {
+    fn load(&self, aggregate_id: &I) -> Vec<MessageEnvelope<A, E>> {
+        self.0.load(aggregate_id)
+    }
+    fn load_since(&self, aggregate_id: &I, after_sequence: usize) -> Vec<MessageEnvelope<A, E>> {
+        self.0.load_since(aggregate_id, after_sequence)
+    }
+    fn commit(&self, events: Vec<MessageEnvelope<A, E>>, expected_sequence: usize) -> Result<(), AggregateError> {
+        self.0.commit(events, expected_sequence)
+    }
+    fn lock(&self, id: &I) -> EventStoreLockGuard {
+        self.0.lock(id)
+    }
+}
+
+/// A point-in-time snapshot of an aggregate, used to short-circuit full event replay.
+///
+/// Implementations are consulted by [`CqrsFramework`](crate::cqrs::CqrsFramework) before
+/// falling back to replaying the raw event stream, and are updated whenever an `execute`
+/// crosses a `snapshot_interval` boundary so later loads can resume from a recent state
+/// instead of sequence zero.
+pub trait SnapshotStore<I: AggregateId<A>, A: Aggregate + Serialize + DeserializeOwned> {
+    /// Returns the most recently saved snapshot for `id`, along with the sequence it was
+    /// taken at, provided it was saved against `expected_version`. Returns `None` both when
+    /// no snapshot exists yet and when one exists but was saved under a different version —
+    /// implementations must treat the latter as stale and let the caller rebuild from the
+    /// event stream instead of trusting it.
+    fn load_snapshot(&self, id: &I, expected_version: u32) -> Option<(A, usize)>;
+    /// Persists `aggregate` as the snapshot for `id` at `sequence`, tagged with `version` so
+    /// a later incompatible shape change can be detected by [`SnapshotStore::load_snapshot`].
+    fn save_snapshot(&self, id: &I, aggregate: &A, sequence: usize, version: u32);
+}
+
+/// A `SnapshotStore` that never has anything to offer, used as the default so existing
+/// stores keep their pure-replay behavior without opting in to snapshotting.
+pub struct NoopSnapshotStore;
+
+impl<I: AggregateId<A>, A: Aggregate + Serialize + DeserializeOwned> SnapshotStore<I, A> for NoopSnapshotStore {
+    fn load_snapshot(&self, _id: &I, _expected_version: u32) -> Option<(A, usize)> {
+        None
+    }
+
+    fn save_snapshot(&self, _id: &I, _aggregate: &A, _sequence: usize, _version: u32) {}
+}
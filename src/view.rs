@@ -0,0 +1,38 @@
+use crate::aggregate::{Aggregate, AggregateError, AggregateId};
+use crate::event::{DomainEvent, MessageEnvelope};
+
+/// Receives the events committed by a successful `execute` call so a read-side projection
+/// can be kept up to date.
+pub trait ViewProcessor<I: AggregateId<A>, A: Aggregate, E: DomainEvent<A>> {
+    /// Applies `events` to whatever projection this processor maintains for `aggregate_id`.
+    fn dispatch(&self, aggregate_id: &I, events: Vec<MessageEnvelope<A, E>>);
+}
+
+/// Inspects the events an `execute` call is about to persist, before they reach the
+/// [`EventStore`](crate::store::EventStore). Returning an error vetoes the whole transaction:
+/// nothing is committed and no post-save listener runs.
+pub trait PreSaveEventListener<I: AggregateId<A>, A: Aggregate, E: DomainEvent<A>> {
+    /// Inspects (and optionally rejects) the events about to be committed for `aggregate_id`.
+    fn handle(&self, aggregate_id: &I, events: &[MessageEnvelope<A, E>]) -> Result<(), AggregateError>;
+}
+
+/// Receives the events an `execute` call has just committed successfully. Every
+/// [`ViewProcessor`] is also a `PostSaveEventListener`, so existing views keep working
+/// unchanged alongside any other listeners registered for cross-cutting concerns such as
+/// outbox enqueueing.
+pub trait PostSaveEventListener<I: AggregateId<A>, A: Aggregate, E: DomainEvent<A>> {
+    /// Handles `events`, which were just committed for `aggregate_id`.
+    fn handle(&self, aggregate_id: &I, events: Vec<MessageEnvelope<A, E>>);
+}
+
+impl<I, A, E, V> PostSaveEventListener<I, A, E> for V
+    where
+        I: AggregateId<A>,
+        A: Aggregate,
+        E: DomainEvent<A>,
+        V: ViewProcessor<I, A, E> + ?Sized
+{
+    fn handle(&self, aggregate_id: &I, events: Vec<MessageEnvelope<A, E>>) {
+        self.dispatch(aggregate_id, events);
+    }
+}